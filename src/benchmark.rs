@@ -0,0 +1,24 @@
+/// The shell commands used to prime, warm up, and then benchmark a single
+/// endpoint under test.
+pub struct BenchmarkCommands {
+    /// A short, low-concurrency command run once before warmup to let the
+    /// server establish connections/caches.
+    pub primer_command: Vec<String>,
+    /// Staged warmup commands that ramp connections up to the run's target
+    /// concurrency before the timed benchmark commands run.
+    pub warmup_command: Vec<Vec<String>>,
+    /// The timed commands used to measure each configured concurrency
+    /// level, one entry per level (same length/order as
+    /// `concurrency_levels`).
+    pub benchmark_commands: Vec<ConcurrencyBenchmarkCommands>,
+}
+
+/// The timed commands generated for a single concurrency level: always a
+/// closed-loop (`wrk`) command, plus an optional open-loop (`wrk2`,
+/// fixed-rate) command at that same concurrency so the two can be compared
+/// for the same test instead of counted as separate levels.
+pub struct ConcurrencyBenchmarkCommands {
+    pub concurrency: u32,
+    pub closed_loop_command: Vec<String>,
+    pub open_loop_command: Option<Vec<String>>,
+}
@@ -0,0 +1,103 @@
+use serde::Deserialize;
+
+/// Tunable parameters for the `Query`/`Updates` verification passes,
+/// loaded from an optional `verifier.toml` in the working directory and
+/// overridable by `VERIFIER__*` environment variables (e.g.
+/// `VERIFIER__REPETITIONS=3`, double underscore since several field names
+/// are themselves underscored), following the same env/TOML layering used
+/// by `nostr-rs-relay` and `chronos`.
+///
+/// Any value not supplied by either source falls back to
+/// [`VerificationSettings::default`], which reproduces the behavior this
+/// verifier had before these knobs were made configurable.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct VerificationSettings {
+    /// Raw `rows` query-string values exercised against the endpoint under
+    /// test, including the malformed/out-of-range cases.
+    pub test_cases: Vec<String>,
+    /// Number of times each load of `concurrency` requests is repeated
+    /// while verifying database-observed effects.
+    pub repetitions: u32,
+    /// Lower clamp applied to a parsed `rows` value.
+    pub min_query_count: u32,
+    /// Upper clamp applied to a parsed `rows` value.
+    pub max_query_count: u32,
+    /// Number of `World` rows fetched/updated per request at the maximum
+    /// query count.
+    pub rows_per_request: u32,
+    /// Maximum number of distinct samples kept for each recurring error
+    /// kind raised during a single verification run before the rest are
+    /// folded into an aggregate "...and N more like this" count.
+    pub error_sample_limit: usize,
+    /// Worker count for the bounded pool that fans out independent
+    /// per-test-case HTTP probing. Defaults to the available core count.
+    pub worker_pool_size: usize,
+    /// Number of distinct values `randomNumber` can be set to by an UPDATE.
+    /// An update that happens to draw the row's existing value is a no-op
+    /// and therefore unobservable when comparing before/after snapshots.
+    pub random_number_value_count: f64,
+    /// Standard-deviation multiplier below the mean observed-update count
+    /// at which `Updates::verify_updates` reports an error.
+    pub update_error_sigma: f64,
+    /// Standard-deviation multiplier below the mean observed-update count
+    /// at which `Updates::verify_updates` reports a warning.
+    pub update_warning_sigma: f64,
+    /// Lower bound for the computed update-count thresholds so a tiny
+    /// `expected_updates` (e.g. a single-digit concurrency level) never
+    /// produces a non-positive threshold.
+    pub min_update_threshold: f64,
+}
+
+impl Default for VerificationSettings {
+    fn default() -> Self {
+        Self {
+            test_cases: vec![
+                "2".to_string(),
+                "0".to_string(),
+                "foo".to_string(),
+                "501".to_string(),
+                "".to_string(),
+            ],
+            repetitions: 2,
+            min_query_count: 1,
+            max_query_count: 500,
+            rows_per_request: 20,
+            error_sample_limit: 3,
+            worker_pool_size: num_cpus::get(),
+            random_number_value_count: 10_000.0,
+            update_error_sigma: 3.0,
+            update_warning_sigma: 2.0,
+            min_update_threshold: 1.0,
+        }
+    }
+}
+
+impl VerificationSettings {
+    /// Loads settings from `verifier.toml` (if present), then applies
+    /// `VERIFIER__*` environment variable overrides, falling back to
+    /// [`VerificationSettings::default`] for anything still unspecified. If
+    /// the layered config can't be built or deserialized (e.g. a malformed
+    /// `verifier.toml`, or an env var that doesn't parse as the field's
+    /// type), the error is printed to stderr and the default is used rather
+    /// than failing silently.
+    pub fn load() -> Self {
+        let settings = config::Config::builder()
+            .add_source(config::File::with_name("verifier").required(false))
+            .add_source(config::Environment::with_prefix("VERIFIER").separator("__"))
+            .build()
+            .and_then(|settings| settings.try_deserialize());
+
+        match settings {
+            Ok(settings) => settings,
+            Err(error) => {
+                eprintln!(
+                    "warning: failed to load verification settings from verifier.toml/VERIFIER__* \
+                     environment variables, falling back to defaults: {}",
+                    error
+                );
+                Self::default()
+            }
+        }
+    }
+}
@@ -1,26 +1,83 @@
-use crate::benchmark::BenchmarkCommands;
+use crate::benchmark::{BenchmarkCommands, ConcurrencyBenchmarkCommands};
+use crate::config::VerificationSettings;
 use crate::database::DatabaseInterface;
 use crate::error::VerifierResult;
 use crate::request::{get_response_body, get_response_headers, ContentType};
 use crate::test_type::query::Query;
 use crate::test_type::Executor;
 use crate::verification::Messages;
+use crate::workpool::Workpool;
 use std::cmp;
 use std::cmp::min;
 
 pub struct Updates {
     pub concurrency_levels: Vec<u32>,
     pub database_verifier: Box<dyn DatabaseInterface>,
+    /// Total wall-clock duration (seconds) over which the warmup run ramps
+    /// connections up from a low starting point to the run's target
+    /// concurrency, rather than applying the full concurrency instantly.
+    /// Ideally this (and `ramp_up_steps`) would live on the shared
+    /// `Executor` trait so every test type inherits ramp-up behavior for
+    /// free; until that refactor lands, it's exposed here on `Updates`.
+    pub ramp_up_duration: u32,
+    /// Number of discrete connection-count steps used to climb from the
+    /// ramp-up starting point to the target concurrency.
+    pub ramp_up_steps: u32,
+    /// Optional per-concurrency-level request rates (requests/sec) for an
+    /// additional open-loop (`wrk2`) benchmark command, indexed in parallel
+    /// with `concurrency_levels`. A rate is typically derived from a prior
+    /// closed-loop throughput probe at that concurrency. `None` means only
+    /// the closed-loop commands are generated.
+    pub open_loop_rates: Option<Vec<u32>>,
+    /// Test case list, repetition count, query clamps, and row-per-request
+    /// factor used while verifying this test type, loaded via
+    /// [`VerificationSettings::load`] so operators can tune them without
+    /// recompiling the verifier.
+    pub settings: VerificationSettings,
+}
+
+/// Load-generation strategy for a single `wrk`/`wrk2` invocation.
+enum LoadMode {
+    /// Stock `wrk` closed-loop mode: each connection issues its next
+    /// request as soon as the previous one completes. Suffers from
+    /// coordinated omission, understating tail latency when the server
+    /// under test stalls.
+    ClosedLoop,
+    /// `wrk2`-style open-loop mode: requests are issued at a fixed `rate`
+    /// regardless of how quickly prior requests complete, and latency is
+    /// measured from each request's intended send time. Corrects for
+    /// coordinated omission, exposing true tail behavior.
+    OpenLoop { rate: u32 },
 }
 impl Query for Updates {}
 impl Executor for Updates {
     fn retrieve_benchmark_commands(&self, url: &str) -> VerifierResult<BenchmarkCommands> {
-        let primer_command = self.get_wrk_command(url, 5, 8);
-        let warmup_command =
-            self.get_wrk_command(url, 15, *self.concurrency_levels.iter().max().unwrap());
+        let primer_command = self.get_wrk_command(url, 5, 8, &LoadMode::ClosedLoop);
+        let warmup_concurrency = *self.concurrency_levels.iter().max().unwrap();
+        let warmup_command = self.get_ramp_up_commands(url, warmup_concurrency);
         let mut benchmark_commands = Vec::default();
-        for concurrency in &self.concurrency_levels {
-            benchmark_commands.push(self.get_wrk_command(url, 15, *concurrency));
+        for (index, concurrency) in self.concurrency_levels.iter().enumerate() {
+            let closed_loop_command =
+                self.get_wrk_command(url, 15, *concurrency, &LoadMode::ClosedLoop);
+
+            // Produce an additional CO-corrected, fixed-rate command
+            // alongside the closed-loop one at the same concurrency so both
+            // can be compared for the same test, rather than appending it as
+            // its own level and losing the 1:1 correspondence with
+            // `concurrency_levels`.
+            let open_loop_command = self
+                .open_loop_rates
+                .as_ref()
+                .and_then(|rates| rates.get(index))
+                .map(|rate| {
+                    self.get_wrk_command(url, 15, *concurrency, &LoadMode::OpenLoop { rate: *rate })
+                });
+
+            benchmark_commands.push(ConcurrencyBenchmarkCommands {
+                concurrency: *concurrency,
+                closed_loop_command,
+                open_loop_command,
+            });
         }
 
         Ok(BenchmarkCommands {
@@ -32,44 +89,73 @@ impl Executor for Updates {
 
     fn verify(&self, url: &str) -> VerifierResult<Messages> {
         let mut messages = Messages::new(url);
+        messages.set_error_sample_limit(self.settings.error_sample_limit);
 
-        let test_cases = ["2", "0", "foo", "501", ""];
+        let settings = &self.settings;
+        let test_cases = &settings.test_cases;
 
         // Initialization for query counting
-        let repetitions = 2;
+        let repetitions = settings.repetitions;
         let concurrency = *self.concurrency_levels.iter().max().unwrap();
-        let expected_rows = 20 * repetitions * concurrency;
+        let expected_rows = settings.rows_per_request * repetitions * concurrency;
         let expected_updates = expected_rows;
         // Note: frameworks are allowed to do the updates in a single bulk query so some frameworks will
         // have only 1 update query for every 20 select queries. so we only need to verify that at least
         // this number of queries were performed.
         // i.e. if concurrency = 1, then we will have:
-        // 20 * 2 = 40 rows updated 
-        // 20 * 2 = 40 select queries 
+        // 20 * 2 = 40 rows updated
+        // 20 * 2 = 40 select queries
         // 1 * 2 = 2 update queries = 42 expected queries in total
-        let expected_queries = expected_rows / 20;
-        let min = 1;
-        let max = 500;
+        let expected_queries = expected_rows / settings.rows_per_request;
+        let min = settings.min_query_count;
+        let max = settings.max_query_count;
 
         let response_headers = get_response_headers(&url, &mut messages)?;
         messages.headers(&response_headers);
         self.verify_headers(&response_headers, &url, ContentType::Json, &mut messages);
 
-        for test_case in test_cases.iter() {
-            let expected_length = self.translate_query_count(*test_case, min, max);
+        // The per-test-case body fetch is the expensive, independent part of
+        // this loop (a fresh HTTP round-trip per case), so it's fanned out
+        // across a bounded pool instead of walked one case at a time. Each
+        // worker fetches into its own `Messages` so concurrent fetches never
+        // contend on the shared one; whatever that worker recorded (a
+        // connection failure, a bad status, etc.) is merged back into the
+        // single shared `messages` on the calling thread below, so nothing
+        // observed during the fetch is lost. The length check and any
+        // resulting error/warning, along with the stateful DB verification,
+        // stay on the calling thread against the single shared `messages`
+        // since they depend on a consistent before/after snapshot.
+        let pool = Workpool::with_size(settings.worker_pool_size);
+        let fetch_results = pool.map(test_cases, |test_case| {
+            let mut fetch_messages = Messages::new(url);
             let count_url = format!("{}{}", url, test_case);
+            let body = get_response_body(&count_url, &mut fetch_messages);
+            (body, fetch_messages)
+        });
+
+        for (test_case, (response_body, fetch_messages)) in test_cases.iter().zip(fetch_results) {
+            messages.merge(fetch_messages);
+
+            let expected_length = self.translate_query_count(test_case, min, max);
 
-            let response_body = get_response_body(&count_url, &mut messages);
             messages.body(&response_body);
             self.verify_with_length(&response_body, expected_length, &mut messages);
 
             // Only check update changes if we're testing the highest number of
             // queries, to ensure that we don't accidentally FAIL for a query
             // that only updates 1 item and happens to set its randomNumber to
-            // the same value it previously held
+            // the same value it previously held. Several configured test
+            // cases can clamp to `max`, so this can run more than once per
+            // verification; the checks themselves always run (they issue
+            // real requests the database-observed counts depend on), and
+            // every error/warning message raised below — including those
+            // raised deeper in `DatabaseInterface`, e.g. by
+            // `issue_multi_query_requests` — is capped per kind by
+            // `messages.finalize_sampling()` once verification completes.
             if expected_length == max {
+                let update_url = format!("{}{}", url, settings.rows_per_request);
                 self.database_verifier.verify_queries_count(
-                    &format!("{}20", url),
+                    &update_url,
                     "world",
                     concurrency,
                     repetitions,
@@ -77,7 +163,7 @@ impl Executor for Updates {
                     &mut messages,
                 );
                 self.database_verifier.verify_rows_count(
-                    &format!("{}20", url),
+                    &update_url,
                     "world",
                     concurrency,
                     repetitions,
@@ -86,22 +172,19 @@ impl Executor for Updates {
                     &mut messages,
                 );
                 self.verify_updates_count(
-                    &format!("{}20", url),
+                    &update_url,
                     "world",
                     concurrency,
                     repetitions,
                     expected_updates,
                     &mut messages,
                 );
-                self.verify_updates(
-                    &format!("{}20", url),
-                    concurrency,
-                    repetitions,
-                    &mut messages,
-                )
+                self.verify_updates(&update_url, concurrency, repetitions, &mut messages)
             }
         }
 
+        messages.finalize_sampling();
+
         Ok(messages)
     }
 }
@@ -180,24 +263,80 @@ impl Updates {
             }
         }
 
+        let settings = &self.settings;
+        let (error_threshold, warning_threshold) = Self::update_confidence_thresholds(
+            expected_updates,
+            settings.random_number_value_count,
+            settings.update_error_sigma,
+            settings.update_warning_sigma,
+            settings.min_update_threshold,
+        );
+
         if updates == 0 {
             messages.error("No items were updated in the database.", "No Updates");
-        } else if updates <= (expected_updates as f32 * 0.90) as i32 {
+        } else if (updates as f64) < error_threshold {
             messages.error(
                 format!(
-                    "Only {} items were updated in the database out of roughly {} expected.",
-                    updates, expected_updates
+                    "Only {} items were updated in the database out of roughly {} expected (expected at least {:.1}, {} standard deviations below the mean).",
+                    updates, expected_updates, error_threshold, settings.update_error_sigma
                 ),
                 "Too Few Updates",
             );
-        } else if updates <= (expected_updates as f32 * 0.95) as i32 {
-            messages.warning(format!("There may have been an error updating the database. Only {} items were updated in the database out of the roughly {} expected.", updates, expected_updates), "Too Few Updates");
+        } else if (updates as f64) < warning_threshold {
+            messages.warning(format!("There may have been an error updating the database. Only {} items were updated in the database out of the roughly {} expected (expected at least {:.1}, {} standard deviations below the mean).", updates, expected_updates, warning_threshold, settings.update_warning_sigma), "Too Few Updates");
         }
     }
 
-    fn get_wrk_command(&self, url: &str, duration: u32, concurrency: u32) -> Vec<String> {
-        vec![
-            "wrk",
+    /// Computes the lower-bound thresholds used by [`Updates::verify_updates`]
+    /// from a normal approximation to the binomial distribution of
+    /// *observed* row changes, rather than a flat fraction of
+    /// `expected_updates`.
+    ///
+    /// Each of the `expected_updates` (`R`) UPDATEs sets `randomNumber` to a
+    /// value drawn uniformly from `random_number_value_count` possibilities,
+    /// so with probability `p0 = 1 / random_number_value_count` the update
+    /// redraws the row's existing value and is unobservable. The number of
+    /// observed changes is then `Binomial(R, 1 - p0)`, with mean
+    /// `μ = R * (1 - p0)` and variance `σ² = R * (1 - p0) * p0`. We error
+    /// below `μ - error_sigma * σ` and warn below `μ - warning_sigma * σ`,
+    /// each clamped at `min_threshold` so small `R` can't push a threshold to
+    /// zero or below. All of the sigma/floor inputs are operator-tunable via
+    /// [`VerificationSettings`]; this takes them by value rather than `&self`
+    /// so the formula itself can be unit tested without a `DatabaseInterface`.
+    fn update_confidence_thresholds(
+        expected_updates: u32,
+        random_number_value_count: f64,
+        error_sigma: f64,
+        warning_sigma: f64,
+        min_threshold: f64,
+    ) -> (f64, f64) {
+        let r = expected_updates as f64;
+        let p0 = 1.0 / random_number_value_count;
+        let mean = r * (1.0 - p0);
+        let std_dev = (r * (1.0 - p0) * p0).sqrt();
+
+        let error_threshold = (mean - error_sigma * std_dev).max(min_threshold);
+        let warning_threshold = (mean - warning_sigma * std_dev).max(min_threshold);
+
+        (error_threshold, warning_threshold)
+    }
+
+    fn get_wrk_command(
+        &self,
+        url: &str,
+        duration: u32,
+        concurrency: u32,
+        mode: &LoadMode,
+    ) -> Vec<String> {
+        let binary = match mode {
+            LoadMode::ClosedLoop => "wrk",
+            // wrk2 is a drop-in, CO-corrected fork of wrk that additionally
+            // understands `-R`.
+            LoadMode::OpenLoop { .. } => "wrk2",
+        };
+
+        let mut command: Vec<String> = vec![
+            binary,
             "-H",
             "Host: tfb-server",
             "-H",
@@ -213,7 +352,67 @@ impl Updates {
             "8",
             "-t",
             &format!("{}", min(concurrency, num_cpus::get() as u32)),
-            url,
-        ].iter().map(|item| item.to_string()).collect()
+        ].iter().map(|item| item.to_string()).collect();
+
+        if let LoadMode::OpenLoop { rate } = mode {
+            command.push("-R".to_string());
+            command.push(format!("{}", rate));
+        }
+
+        command.push(url.to_string());
+        command
+    }
+
+    /// Builds a staged sequence of `wrk` warmup invocations that climbs from
+    /// a low connection count up to `target_concurrency` over
+    /// `self.ramp_up_duration` seconds using `self.ramp_up_steps` discrete
+    /// steps, instead of a single command that jumps straight to the target.
+    /// This gives the server under test time to JIT/pool-warm before the
+    /// full concurrency is applied.
+    fn get_ramp_up_commands(&self, url: &str, target_concurrency: u32) -> Vec<Vec<String>> {
+        let steps = cmp::max(self.ramp_up_steps, 1);
+        let step_duration = cmp::max(self.ramp_up_duration / steps, 1);
+        let start_concurrency = cmp::max(target_concurrency / steps, 1);
+
+        (1..=steps)
+            .map(|step| {
+                // The last step always lands exactly on `target_concurrency`
+                // rather than `start_concurrency * steps`, which truncates
+                // short of the target whenever `target_concurrency` isn't an
+                // exact multiple of `steps` (e.g. target 100 over 3 steps
+                // would otherwise ramp 33/66/99, never reaching 100).
+                let concurrency = if step == steps {
+                    target_concurrency
+                } else {
+                    cmp::min(start_concurrency * step, target_concurrency)
+                };
+                self.get_wrk_command(url, step_duration, concurrency, &LoadMode::ClosedLoop)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_confidence_thresholds_uses_normal_approximation() {
+        let (error_threshold, warning_threshold) =
+            Updates::update_confidence_thresholds(1000, 10_000.0, 3.0, 2.0, 1.0);
+
+        // p0 = 1/10_000, so mean ≈ 999.9 and std_dev ≈ sqrt(1000 * 0.9999 * 0.0001) ≈ 0.316.
+        assert!(error_threshold < warning_threshold);
+        assert!((998.9..999.5).contains(&error_threshold));
+        assert!((999.2..999.9).contains(&warning_threshold));
+    }
+
+    #[test]
+    fn update_confidence_thresholds_floors_at_min_threshold() {
+        let (error_threshold, warning_threshold) =
+            Updates::update_confidence_thresholds(1, 10_000.0, 3.0, 2.0, 1.0);
+
+        assert_eq!(error_threshold, 1.0);
+        assert_eq!(warning_threshold, 1.0);
     }
 }
@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+use std::fmt;
+
+/// Default cap on distinct reports kept per recurring (severity, kind) pair
+/// before the rest are folded into an aggregate count, borrowing the
+/// sampling-limit idea from latte's error reporting. Overridable per run via
+/// [`Messages::set_error_sample_limit`].
+const DEFAULT_ERROR_SAMPLE_LIMIT: usize = 3;
+
+/// Accumulates the diagnostics produced while verifying a single endpoint:
+/// the request/response metadata observed along the way (headers, body),
+/// plus any error/warning reports raised while checking them.
+///
+/// Repeated reports of the same `(severity, kind)` are capped at
+/// `error_sample_limit` verbatim entries by [`Messages::finalize_sampling`];
+/// anything past that is folded into a trailing "...and N more like this"
+/// report. Every caller that goes through [`Messages::error`] or
+/// [`Messages::warning`] is covered automatically, including code this
+/// crate doesn't own (e.g. `DatabaseInterface::issue_multi_query_requests`),
+/// since the cap lives here rather than at each call site.
+pub struct Messages {
+    url: String,
+    headers: Option<String>,
+    body: Option<String>,
+    reports: Vec<Report>,
+    error_sample_limit: usize,
+    sampled: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone)]
+struct Report {
+    severity: Severity,
+    kind: String,
+    message: String,
+}
+
+impl Messages {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            headers: None,
+            body: None,
+            reports: Vec::new(),
+            error_sample_limit: DEFAULT_ERROR_SAMPLE_LIMIT,
+            sampled: false,
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Overrides the default cap on distinct samples kept per recurring
+    /// `(severity, kind)` pair for this run. Has no effect until
+    /// [`Messages::finalize_sampling`] is called.
+    pub fn set_error_sample_limit(&mut self, error_sample_limit: usize) {
+        self.error_sample_limit = error_sample_limit;
+    }
+
+    /// Records the response headers observed for this endpoint, formatted
+    /// via `Debug` since the concrete header type varies by caller.
+    pub fn headers<T: fmt::Debug>(&mut self, headers: &T) {
+        self.headers = Some(format!("{:?}", headers));
+    }
+
+    pub fn body(&mut self, body: &str) {
+        self.body = Some(body.to_string());
+    }
+
+    pub fn error(&mut self, message: impl Into<String>, kind: &str) {
+        self.reports.push(Report {
+            severity: Severity::Error,
+            kind: kind.to_string(),
+            message: message.into(),
+        });
+    }
+
+    pub fn warning(&mut self, message: impl Into<String>, kind: &str) {
+        self.reports.push(Report {
+            severity: Severity::Warning,
+            kind: kind.to_string(),
+            message: message.into(),
+        });
+    }
+
+    /// Folds another `Messages`' observed state into this one: headers/body
+    /// are taken only if this one doesn't already have them (first fetch to
+    /// respond wins), and all of its reports are carried over so nothing a
+    /// worker observed independently gets lost.
+    pub fn merge(&mut self, other: Messages) {
+        if self.headers.is_none() {
+            self.headers = other.headers;
+        }
+        if self.body.is_none() {
+            self.body = other.body;
+        }
+        self.reports.extend(other.reports);
+    }
+
+    /// Caps repeated reports of the same `(severity, kind)` pair at
+    /// `error_sample_limit` verbatim entries, folding the rest into a
+    /// trailing "...and N more like this" report. Call once verification
+    /// for an endpoint is complete; calling it again is safe but a no-op
+    /// since it returns early once sampling has been applied.
+    pub fn finalize_sampling(&mut self) {
+        if self.sampled {
+            return;
+        }
+        self.sampled = true;
+        let limit = self.error_sample_limit;
+        let mut counts: HashMap<(Severity, String), usize> = HashMap::new();
+        let mut kept = Vec::with_capacity(self.reports.len());
+
+        for report in self.reports.drain(..) {
+            let count = counts
+                .entry((report.severity, report.kind.clone()))
+                .or_insert(0);
+            *count += 1;
+            if *count <= limit {
+                kept.push(report);
+            }
+        }
+
+        for ((severity, kind), count) in counts {
+            if count > limit {
+                let suppressed = count - limit;
+                kept.push(Report {
+                    severity,
+                    message: format!(
+                        "...and {} more occurrences of \"{}\" like this were suppressed.",
+                        suppressed, kind
+                    ),
+                    kind,
+                });
+            }
+        }
+
+        self.reports = kept;
+    }
+}
+
+impl Default for Messages {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_keeps_first_headers_and_body_and_carries_reports() {
+        let mut messages = Messages::new("http://example.test/");
+        messages.headers(&"first");
+        messages.body("first body");
+
+        let mut other = Messages::new("http://example.test/");
+        other.headers(&"second");
+        other.body("second body");
+        other.error("boom", "Connection Refused");
+
+        messages.merge(other);
+
+        assert_eq!(messages.headers.as_deref(), Some("\"first\""));
+        assert_eq!(messages.body.as_deref(), Some("first body"));
+        assert_eq!(messages.reports.len(), 1);
+        assert_eq!(messages.reports[0].kind, "Connection Refused");
+    }
+
+    #[test]
+    fn finalize_sampling_caps_repeated_kind_and_reports_overflow() {
+        let mut messages = Messages::new("http://example.test/");
+        messages.set_error_sample_limit(2);
+        for _ in 0..5 {
+            messages.error("boom", "Connection Refused");
+        }
+
+        messages.finalize_sampling();
+
+        let verbatim = messages
+            .reports
+            .iter()
+            .filter(|report| report.kind == "Connection Refused" && report.message == "boom")
+            .count();
+        assert_eq!(verbatim, 2);
+
+        let overflow = messages
+            .reports
+            .iter()
+            .find(|report| report.message.contains("more occurrences"))
+            .expect("expected an overflow report");
+        assert!(overflow.message.contains("3 more"));
+    }
+
+    #[test]
+    fn finalize_sampling_leaves_distinct_kinds_untouched() {
+        let mut messages = Messages::new("http://example.test/");
+        messages.set_error_sample_limit(2);
+        messages.error("a", "Kind A");
+        messages.warning("b", "Kind B");
+
+        messages.finalize_sampling();
+
+        assert_eq!(messages.reports.len(), 2);
+    }
+
+    #[test]
+    fn merge_then_finalize_sampling_caps_across_sources() {
+        let mut messages = Messages::new("http://example.test/");
+        messages.set_error_sample_limit(2);
+
+        let mut fetch_messages = Messages::new("http://example.test/");
+        fetch_messages.error("boom", "Connection Refused");
+        messages.merge(fetch_messages);
+
+        messages.error("boom", "Connection Refused");
+        messages.error("boom", "Connection Refused");
+
+        messages.finalize_sampling();
+
+        let connection_refused_reports = messages
+            .reports
+            .iter()
+            .filter(|report| report.kind == "Connection Refused")
+            .count();
+        // 2 verbatim samples + 1 overflow report covering the 3rd occurrence.
+        assert_eq!(connection_refused_reports, 3);
+    }
+}
@@ -0,0 +1,108 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// A bounded pool of scoped worker threads used to fan independent units of
+/// work out across the available cores, modeled on skytable's `Workpool`.
+/// Work items are pulled from a shared index rather than pre-partitioned,
+/// so a slow item on one worker doesn't starve the others.
+pub struct Workpool {
+    size: usize,
+}
+
+impl Workpool {
+    /// Creates a pool sized to `num_cpus::get()`.
+    pub fn new() -> Self {
+        Self::with_size(num_cpus::get())
+    }
+
+    /// Creates a pool with an explicit worker count (clamped to at least 1).
+    pub fn with_size(size: usize) -> Self {
+        Self {
+            size: size.max(1),
+        }
+    }
+
+    /// Runs `job` once per item in `items`, across up to `self.size` worker
+    /// threads, returning the results in the same order as `items`. Uses a
+    /// scoped thread pool so `job` may freely borrow from the calling
+    /// stack frame (e.g. `&self` of the caller).
+    pub fn map<T, R, F>(&self, items: &[T], job: F) -> Vec<R>
+    where
+        T: Sync,
+        R: Send,
+        F: Fn(&T) -> R + Sync,
+    {
+        let next_index = AtomicUsize::new(0);
+        let results: Vec<Mutex<Option<R>>> = (0..items.len()).map(|_| Mutex::new(None)).collect();
+        let workers = self.size.min(items.len().max(1));
+
+        std::thread::scope(|scope| {
+            for _ in 0..workers {
+                let next_index = &next_index;
+                let results = &results;
+                let job = &job;
+                scope.spawn(move || loop {
+                    let index = next_index.fetch_add(1, Ordering::SeqCst);
+                    if index >= items.len() {
+                        break;
+                    }
+                    let result = job(&items[index]);
+                    *results[index]
+                        .lock()
+                        .expect("workpool result lock poisoned") = Some(result);
+                });
+            }
+        });
+
+        results
+            .into_iter()
+            .map(|cell| {
+                cell.into_inner()
+                    .expect("workpool result lock poisoned")
+                    .expect("workpool job did not run for this item")
+            })
+            .collect()
+    }
+}
+
+impl Default for Workpool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_preserves_item_order() {
+        let pool = Workpool::with_size(4);
+        let items: Vec<u32> = (0..50).collect();
+
+        let results = pool.map(&items, |item| item * 2);
+
+        let expected: Vec<u32> = items.iter().map(|item| item * 2).collect();
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn with_size_clamps_to_at_least_one() {
+        let pool = Workpool::with_size(0);
+        let items = vec![1, 2, 3];
+
+        let results = pool.map(&items, |item| *item);
+
+        assert_eq!(results, items);
+    }
+
+    #[test]
+    fn map_handles_empty_input() {
+        let pool = Workpool::new();
+        let items: Vec<u32> = Vec::new();
+
+        let results = pool.map(&items, |item| *item);
+
+        assert!(results.is_empty());
+    }
+}